@@ -3,39 +3,203 @@ use figlet_rs::FIGfont;
 use humantime::parse_duration;
 use iocraft::prelude::*;
 use rodio::{OutputStream, Sink};
-use std::fs::File;
-use std::io::BufReader;
-use std::path::PathBuf;
+use serde::Deserialize;
+use std::collections::VecDeque;
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::thread;
 use std::time::Duration;
+use symphonia::core::audio::SampleBuffer;
+use symphonia::core::codecs::{Decoder, DecoderOptions, CODEC_TYPE_NULL};
+use symphonia::core::formats::{FormatOptions, FormatReader};
+use symphonia::core::io::MediaSourceStream;
+use symphonia::core::meta::MetadataOptions;
+use symphonia::core::probe::Hint;
 
 const FIGLET_MIN_WIDTH: usize = 60;
+const VOLUME_STEP: f32 = 0.1;
+const VOLUME_BAR_SEGMENTS: usize = 5;
 
-fn find_sound_file() -> Option<PathBuf> {
-    if PathBuf::from("sound.mp3").exists() {
-        return Some(PathBuf::from("sound.mp3"));
+fn volume_file() -> Option<PathBuf> {
+    let proj_dirs = ProjectDirs::from("com", "brownbishop", "timer")?;
+    Some(proj_dirs.data_dir().join("volume.txt"))
+}
+
+fn load_volume() -> f32 {
+    volume_file()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|contents| contents.trim().parse::<f32>().ok())
+        .map(|v| v.clamp(0.0, 1.0))
+        .unwrap_or(1.0)
+}
+
+fn save_volume(volume: f32) {
+    if let Some(path) = volume_file() {
+        if let Some(parent) = path.parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+        let _ = fs::write(path, volume.to_string());
     }
+}
+
+fn volume_bar(volume: f32) -> String {
+    let filled = ((volume * VOLUME_BAR_SEGMENTS as f32).round() as usize).min(VOLUME_BAR_SEGMENTS);
+    format!(
+        "{}{}",
+        "▮".repeat(filled),
+        "▯".repeat(VOLUME_BAR_SEGMENTS - filled)
+    )
+}
+
+const SOUND_EXTENSIONS: &[&str] = &["mp3", "wav", "flac", "ogg", "m4a"];
 
+fn candidate_sound_dirs() -> Vec<PathBuf> {
+    let mut dirs = vec![PathBuf::from(".")];
 
     if let Some(proj_dirs) = ProjectDirs::from("com", "brownbishop", "timer") {
-        let data_path = proj_dirs.data_dir().join("sound.mp3");
-        if data_path.exists() {
-            return Some(data_path);
-        }
+        dirs.push(proj_dirs.data_dir().to_path_buf());
     }
 
     if let Ok(exe_path) = std::env::current_exe() {
         if let Some(exe_dir) = exe_path.parent() {
-            let exe_sound = exe_dir.join("sound.mp3");
-            if exe_sound.exists() {
-                return Some(exe_sound);
+            dirs.push(exe_dir.to_path_buf());
+        }
+    }
+
+    dirs
+}
+
+fn find_sound_file() -> Result<PathBuf, String> {
+    let dirs = candidate_sound_dirs();
+
+    for dir in &dirs {
+        for ext in SOUND_EXTENSIONS {
+            let candidate = dir.join(format!("sound.{}", ext));
+            if candidate.exists() {
+                return Ok(candidate);
             }
         }
     }
 
-    None
+    let tried: Vec<String> = dirs
+        .iter()
+        .flat_map(|dir| {
+            SOUND_EXTENSIONS
+                .iter()
+                .map(move |ext| dir.join(format!("sound.{}", ext)).display().to_string())
+        })
+        .collect();
+
+    Err(format!(
+        "Could not find a sound file in any of these locations:\n  - {}",
+        tried.join("\n  - ")
+    ))
+}
+
+struct SymphoniaSource {
+    decoder: Box<dyn Decoder>,
+    format_reader: Box<dyn FormatReader>,
+    track_id: u32,
+    sample_rate: u32,
+    channels: u16,
+    buffer: VecDeque<i16>,
+}
+
+fn open_symphonia_source(path: &Path) -> Result<SymphoniaSource, String> {
+    let file =
+        File::open(path).map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    let mss = MediaSourceStream::new(Box::new(file), Default::default());
+
+    let mut hint = Hint::new();
+    if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
+        hint.with_extension(ext);
+    }
+
+    let probed = symphonia::default::get_probe()
+        .format(
+            &hint,
+            mss,
+            &FormatOptions::default(),
+            &MetadataOptions::default(),
+        )
+        .map_err(|e| format!("Failed to probe {}: {}", path.display(), e))?;
+
+    let format_reader = probed.format;
+
+    let track = format_reader
+        .tracks()
+        .iter()
+        .find(|t| t.codec_params.codec != CODEC_TYPE_NULL)
+        .ok_or_else(|| format!("No supported audio track in {}", path.display()))?
+        .clone();
+
+    let decoder = symphonia::default::get_codecs()
+        .make(&track.codec_params, &DecoderOptions::default())
+        .map_err(|e| format!("Failed to create decoder for {}: {}", path.display(), e))?;
+
+    let sample_rate = track
+        .codec_params
+        .sample_rate
+        .ok_or_else(|| format!("Unknown sample rate in {}", path.display()))?;
+    let channels = track
+        .codec_params
+        .channels
+        .ok_or_else(|| format!("Unknown channel layout in {}", path.display()))?
+        .count() as u16;
+
+    Ok(SymphoniaSource {
+        decoder,
+        format_reader,
+        track_id: track.id,
+        sample_rate,
+        channels,
+        buffer: VecDeque::new(),
+    })
+}
+
+impl Iterator for SymphoniaSource {
+    type Item = i16;
+
+    fn next(&mut self) -> Option<i16> {
+        loop {
+            if let Some(sample) = self.buffer.pop_front() {
+                return Some(sample);
+            }
+
+            let packet = self.format_reader.next_packet().ok()?;
+            if packet.track_id() != self.track_id {
+                continue;
+            }
+
+            if let Ok(decoded) = self.decoder.decode(&packet) {
+                let spec = *decoded.spec();
+                let duration = decoded.capacity() as u64;
+                let mut sample_buf = SampleBuffer::<i16>::new(duration, spec);
+                sample_buf.copy_interleaved_ref(decoded);
+                self.buffer.extend(sample_buf.samples().iter().copied());
+            }
+        }
+    }
+}
+
+impl rodio::Source for SymphoniaSource {
+    fn current_frame_len(&self) -> Option<usize> {
+        None
+    }
+
+    fn channels(&self) -> u16 {
+        self.channels
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        None
+    }
 }
 
 fn format_duration_hms(duration: Duration) -> String {
@@ -50,16 +214,124 @@ fn format_duration_figlet(hms: &str) -> Option<String> {
     Some(FIGfont::standard().ok()?.convert(hms)?.to_string())
 }
 
+fn parse_phase(arg: &str) -> (Duration, String) {
+    match arg.split_once(':') {
+        Some((dur_str, label)) => {
+            let duration = parse_duration(dur_str)
+                .unwrap_or_else(|_| panic!("Invalid duration format in '{}'. Examples: '30s', '1m', '1h30m'", arg));
+            (duration, label.to_string())
+        }
+        None => {
+            let duration = parse_duration(arg)
+                .unwrap_or_else(|_| panic!("Invalid duration format in '{}'. Examples: '30s', '1m', '1h30m'", arg));
+            (duration, "Timer".to_string())
+        }
+    }
+}
+
+fn parse_phases(args: &[String], default_duration: Duration) -> Vec<(Duration, String)> {
+    if args.is_empty() {
+        return vec![(default_duration, "Timer".to_string())];
+    }
+    args.iter().map(|arg| parse_phase(arg)).collect()
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct Config {
+    default_duration: Option<String>,
+    sound_file: Option<PathBuf>,
+    border_color: Option<String>,
+    running_color: Option<String>,
+    paused_color: Option<String>,
+    figlet_min_width: Option<usize>,
+    /// Alarm repeat count, e.g. `repeat = "3"`. TOML strings must be quoted,
+    /// so an infinite loop is `repeat = "loop"` (not the bare `loop`).
+    repeat: Option<String>,
+}
+
+fn config_file() -> Option<PathBuf> {
+    let proj_dirs = ProjectDirs::from("com", "brownbishop", "timer")?;
+    Some(proj_dirs.config_dir().join("config.toml"))
+}
+
+fn load_config() -> Config {
+    let Some(path) = config_file() else {
+        return Config::default();
+    };
+    let Ok(contents) = fs::read_to_string(&path) else {
+        return Config::default();
+    };
+    match toml::from_str(&contents) {
+        Ok(config) => config,
+        Err(err) => {
+            eprintln!(
+                "warning: ignoring config file {} ({})",
+                path.display(),
+                err
+            );
+            Config::default()
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Repeat {
+    Count(u32),
+    Infinite,
+}
+
+impl Default for Repeat {
+    fn default() -> Self {
+        Repeat::Count(1)
+    }
+}
+
+fn parse_repeat(value: &str) -> Repeat {
+    if value.eq_ignore_ascii_case("loop") {
+        Repeat::Infinite
+    } else {
+        value.parse().map(Repeat::Count).unwrap_or_default()
+    }
+}
+
+fn parse_color(name: &str) -> Option<Color> {
+    match name.to_lowercase().as_str() {
+        "black" => Some(Color::Black),
+        "red" => Some(Color::Red),
+        "green" => Some(Color::Green),
+        "yellow" => Some(Color::Yellow),
+        "blue" => Some(Color::Blue),
+        "magenta" => Some(Color::Magenta),
+        "cyan" => Some(Color::Cyan),
+        "white" => Some(Color::White),
+        "grey" | "gray" => Some(Color::Grey),
+        "darkgrey" | "darkgray" => Some(Color::DarkGrey),
+        _ => None,
+    }
+}
+
 #[derive(Default, Props)]
 struct CounterProps {
-    duration: Duration,
+    phases: Vec<(Duration, String)>,
     sound_file: PathBuf,
+    border_color: Option<Color>,
+    running_color: Option<Color>,
+    paused_color: Option<Color>,
+    figlet_min_width: usize,
+    repeat: Repeat,
 }
 
 #[component]
 fn Timer(props: &mut CounterProps, mut hooks: Hooks) -> impl Into<AnyElement<'static>> {
-    let mut remaining = hooks.use_state(|| props.duration);
+    let phases = props.phases.clone();
+    let phase_count = phases.len();
+    let mut phase_index = hooks.use_state(|| 0usize);
+    let mut remaining = hooks.use_state(|| phases[0].0);
     let mut playing = hooks.use_state(|| false);
+    let mut paused = hooks.use_state(|| false);
+    let mut volume = hooks.use_state(load_volume);
+    let mut blink = hooks.use_state(|| false);
+    let mut blink_tick = hooks.use_state(|| 0u32);
     let mut should_exit = hooks.use_state(|| false);
     let mut system = hooks.use_context_mut::<SystemContext>();
     let (width, height) = hooks.use_terminal_size();
@@ -67,45 +339,88 @@ fn Timer(props: &mut CounterProps, mut hooks: Hooks) -> impl Into<AnyElement<'st
     let finished_signal = hooks.use_ref(|| Arc::new(AtomicBool::new(false)));
 
     let sound_file = props.sound_file.clone();
+    let repeat = props.repeat;
 
     hooks.use_future(async move {
         loop {
             smol::Timer::after(Duration::from_millis(1000)).await;
-            if !playing.get() && !remaining.get().is_zero() {
+            if !playing.get() && !paused.get() && !remaining.get().is_zero() {
                 remaining.set(remaining.get().saturating_sub(Duration::from_secs(1)));
             }
         }
     });
 
-    hooks.use_future(async move {
-        loop {
-            smol::Timer::after(Duration::from_millis(100)).await;
-            if remaining.get().is_zero() && !playing.get() {
-                let stop = stop_signal.read().clone();
-                let finished = finished_signal.read().clone();
-                let sound_file = sound_file.clone();
-                thread::spawn(move || {
-                    if let Ok((stream, stream_handle)) = OutputStream::try_default() {
-                        if let Ok(sink) = Sink::try_new(&stream_handle) {
-                            if let Ok(file) = File::open(&sound_file) {
-                                if let Ok(source) = rodio::Decoder::new(BufReader::new(file)) {
-                                    sink.append(source);
-                                    while !sink.empty() && !stop.load(Ordering::Relaxed) {
-                                        thread::sleep(Duration::from_millis(50));
+    hooks.use_future({
+        let phases = phases.clone();
+        async move {
+            loop {
+                smol::Timer::after(Duration::from_millis(100)).await;
+                if remaining.get().is_zero() && !playing.get() {
+                    let stop = stop_signal.read().clone();
+                    let finished = finished_signal.read().clone();
+                    let sound_file = sound_file.clone();
+                    let volume = volume.get();
+                    // Only the final phase's alarm honors an infinite/large
+                    // repeat count — an intermediate phase boundary must
+                    // still advance the sequence, so it always plays once.
+                    let is_last_phase = phase_index.get() + 1 == phases.len();
+                    let repeat = if is_last_phase {
+                        repeat
+                    } else {
+                        Repeat::Count(1)
+                    };
+                    thread::spawn(move || {
+                        if let Ok((stream, stream_handle)) = OutputStream::try_default() {
+                            if let Ok(sink) = Sink::try_new(&stream_handle) {
+                                sink.set_volume(volume);
+                                if !matches!(repeat, Repeat::Count(0)) {
+                                    let mut played = 0u32;
+                                    while !stop.load(Ordering::Relaxed) {
+                                        match open_symphonia_source(&sound_file) {
+                                            Ok(source) => sink.append(source),
+                                            Err(_) => break,
+                                        }
+                                        while !sink.empty() && !stop.load(Ordering::Relaxed) {
+                                            thread::sleep(Duration::from_millis(50));
+                                        }
+                                        played += 1;
+                                        if let Repeat::Count(n) = repeat {
+                                            if played >= n {
+                                                break;
+                                            }
+                                        }
                                     }
-                                    sink.stop();
                                 }
+                                sink.stop();
+                                drop(sink);
                             }
-                            drop(sink);
+                            drop(stream);
                         }
-                        drop(stream);
+                        finished.store(true, Ordering::Relaxed);
+                    });
+                    playing.set(true);
+                }
+                if playing.get() {
+                    let next_tick = blink_tick.get() + 1;
+                    blink_tick.set(next_tick);
+                    if next_tick % 5 == 0 {
+                        blink.set(!blink.get());
                     }
-                    finished.store(true, Ordering::Relaxed);
-                });
-                playing.set(true);
-            }
-            if playing.get() && finished_signal.read().load(Ordering::Relaxed) {
-                should_exit.set(true);
+                } else {
+                    blink_tick.set(0);
+                    blink.set(false);
+                }
+                if playing.get() && finished_signal.read().load(Ordering::Relaxed) {
+                    let next_index = phase_index.get() + 1;
+                    if next_index < phases.len() {
+                        phase_index.set(next_index);
+                        remaining.set(phases[next_index].0);
+                        playing.set(false);
+                        finished_signal.read().store(false, Ordering::Relaxed);
+                    } else {
+                        should_exit.set(true);
+                    }
+                }
             }
         }
     });
@@ -119,6 +434,26 @@ fn Timer(props: &mut CounterProps, mut hooks: Hooks) -> impl Into<AnyElement<'st
                         stop_signal.read().store(true, Ordering::Relaxed);
                         should_exit.set(true);
                     }
+                    KeyCode::Char(' ') => {
+                        if !playing.get() {
+                            paused.set(!paused.get());
+                        }
+                    }
+                    KeyCode::Up | KeyCode::Char('+') => {
+                        let new_volume = (volume.get() + VOLUME_STEP).clamp(0.0, 1.0);
+                        volume.set(new_volume);
+                        save_volume(new_volume);
+                    }
+                    KeyCode::Down | KeyCode::Char('-') => {
+                        let new_volume = (volume.get() - VOLUME_STEP).clamp(0.0, 1.0);
+                        volume.set(new_volume);
+                        save_volume(new_volume);
+                    }
+                    KeyCode::Char('n') => {
+                        if !playing.get() {
+                            remaining.set(Duration::ZERO);
+                        }
+                    }
                     _ => {}
                 }
             }
@@ -130,16 +465,45 @@ fn Timer(props: &mut CounterProps, mut hooks: Hooks) -> impl Into<AnyElement<'st
         system.exit();
     }
 
-    let text_color = if playing.get() { Color::Red } else { Color::Blue };
+    let border_color = props.border_color.unwrap_or(Color::Green);
+    let running_color = props.running_color.unwrap_or(Color::Blue);
+    let paused_color = props.paused_color.unwrap_or(Color::Yellow);
+
+    let text_color = if playing.get() {
+        Color::Red
+    } else if paused.get() {
+        paused_color
+    } else {
+        running_color
+    };
+
+    let status_text = if playing.get() {
+        if blink.get() {
+            "TIME'S UP"
+        } else {
+            ""
+        }
+    } else if paused.get() {
+        "⏸ PAUSED"
+    } else {
+        "▶ RUNNING"
+    };
 
     let hms = format_duration_hms(remaining.get());
-    let use_figlet = usize::from(width) >= FIGLET_MIN_WIDTH;
+    let use_figlet = usize::from(width) >= props.figlet_min_width;
     let display_text = if use_figlet {
         format_duration_figlet(&hms).unwrap_or(hms)
     } else {
         hms
     };
 
+    let phase_label = format!(
+        "{} ({}/{})",
+        phases[phase_index.get()].1,
+        phase_index.get() + 1,
+        phase_count
+    );
+
     element! {
         View(
             width,
@@ -149,22 +513,78 @@ fn Timer(props: &mut CounterProps, mut hooks: Hooks) -> impl Into<AnyElement<'st
             align_items: AlignItems::Center,
         ) {
             View(
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
                 border_style: BorderStyle::Round,
-                border_color: Color::Green,
+                border_color,
             ) {
+                Text(color: Color::Grey, content: phase_label)
                 Text(color: text_color, content: display_text)
+                Text(color: text_color, content: status_text)
+                Text(color: Color::Grey, content: format!("vol {}", volume_bar(volume.get())))
             }
         }
     }
 }
 
 fn main() {
-    let duration = std::env::args()
-        .nth(1)
-        .map(|s| parse_duration(&s).expect("Invalid duration format. Examples: '30s', '1m', '1h30m'"))
+    let config = load_config();
+
+    let default_duration = config
+        .default_duration
+        .as_deref()
+        .and_then(|s| parse_duration(s).ok())
         .unwrap_or(Duration::from_secs(60));
 
-    let sound_file = find_sound_file().expect("Could not find sound.mp3 in any of these locations:\n  - ./sound.mp3 (current directory)\n  - <data_dir>/timer/sound.mp3\n  - <executable_dir>/sound.mp3");
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    let mut repeat_flag: Option<String> = None;
+    let mut i = 0;
+    while i < args.len() {
+        if args[i] == "--repeat" {
+            args.remove(i);
+            if i < args.len() {
+                repeat_flag = Some(args.remove(i));
+            }
+        } else if let Some(value) = args[i].strip_prefix("--repeat=") {
+            repeat_flag = Some(value.to_string());
+            args.remove(i);
+        } else {
+            i += 1;
+        }
+    }
+
+    let repeat = repeat_flag
+        .as_deref()
+        .or(config.repeat.as_deref())
+        .map(parse_repeat)
+        .unwrap_or_default();
+
+    let phases = parse_phases(&args, default_duration);
+
+    let sound_file = config
+        .sound_file
+        .clone()
+        .filter(|path| path.exists())
+        .map(Ok)
+        .unwrap_or_else(find_sound_file)
+        .unwrap_or_else(|err| panic!("{}", err));
+
+    let border_color = config.border_color.as_deref().and_then(parse_color);
+    let running_color = config.running_color.as_deref().and_then(parse_color);
+    let paused_color = config.paused_color.as_deref().and_then(parse_color);
+    let figlet_min_width = config.figlet_min_width.unwrap_or(FIGLET_MIN_WIDTH);
 
-    smol::block_on(element!(Timer(duration, sound_file)).render_loop()).unwrap()
+    smol::block_on(
+        element!(Timer(
+            phases,
+            sound_file,
+            border_color,
+            running_color,
+            paused_color,
+            figlet_min_width,
+            repeat,
+        ))
+        .render_loop(),
+    )
+    .unwrap()
 }